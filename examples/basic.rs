@@ -59,7 +59,10 @@ fn do_analysis(dataset_chosen: &DatasetData, adj_method: AdjustmentMethod) -> Py
         adjustment_method: adj_method,
         is_all_vs_all,
         collect_gem_dataset,
-        keep_top_n
+        keep_top_n,
+        threads: None,
+        covariate_file_path: None,
+        result_db_path: None,
     };
 
     let now = Instant::now();
@@ -0,0 +1,88 @@
+/// Method used to correct p-values for multiple comparisons.
+#[derive(Clone, Debug)]
+pub enum AdjustmentMethod {
+    BenjaminiHochberg = 1,
+    BenjaminiYekutieli = 2,
+    Bonferroni = 3,
+}
+
+impl std::fmt::Display for AdjustmentMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let description = match &self {
+            AdjustmentMethod::BenjaminiHochberg => "Benjamini-Hochberg",
+            AdjustmentMethod::BenjaminiYekutieli => "Benjamini-Yekutieli",
+            AdjustmentMethod::Bonferroni => "Bonferroni",
+        };
+
+        write!(f, "{description}")
+    }
+}
+
+/// Adjusts a slice of p-values that is already sorted in ascending order, writing the result
+/// in place. Each p-value's 1-based rank is its position in the sorted slice, and `m`
+/// is the total number of hypotheses tested (which may be greater than `p_values.len()` when
+/// some combinations were filtered out before reaching this pass).
+pub fn adjust(method: &AdjustmentMethod, p_values: &mut [f64], m: usize) {
+    match method {
+        AdjustmentMethod::BenjaminiHochberg => benjamini_hochberg(p_values, m, 1.0),
+        AdjustmentMethod::BenjaminiYekutieli => {
+            let harmonic_sum: f64 = (1..=m).map(|i| 1.0 / i as f64).sum();
+            benjamini_hochberg(p_values, m, harmonic_sum);
+        }
+        AdjustmentMethod::Bonferroni => {
+            for p_value in p_values.iter_mut() {
+                *p_value = (*p_value * m as f64).min(1.0);
+            }
+        }
+    }
+}
+
+/// Shared step-up procedure for Benjamini-Hochberg and Benjamini-Yekutieli, which only differ
+/// in the `correction_factor` applied to every term (1.0 for BH, the harmonic sum for BY).
+fn benjamini_hochberg(p_values: &mut [f64], m: usize, correction_factor: f64) {
+    let mut running_min = 1.0;
+    for (i, p_value) in p_values.iter_mut().enumerate().rev() {
+        let rank = (i + 1) as f64;
+        let adjusted = (*p_value * m as f64 * correction_factor / rank).min(1.0);
+        running_min = running_min.min(adjusted);
+        *p_value = running_min;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn benjamini_hochberg_known_values() {
+        // p_i / i is constant (0.01) across the whole sequence, so every adjusted p-value
+        // collapses to the same number: 0.01 * m.
+        let mut p_values = vec![0.01, 0.02, 0.03, 0.04, 0.05];
+        adjust(&AdjustmentMethod::BenjaminiHochberg, &mut p_values, 5);
+
+        for p_value in p_values {
+            assert!((p_value - 0.05).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn benjamini_yekutieli_known_values() {
+        let mut p_values = vec![0.01, 0.02, 0.03, 0.04, 0.05];
+        adjust(&AdjustmentMethod::BenjaminiYekutieli, &mut p_values, 5);
+
+        let harmonic_sum = 1.0 + 1.0 / 2.0 + 1.0 / 3.0 + 1.0 / 4.0 + 1.0 / 5.0;
+        let expected = 0.01 * 5.0 * harmonic_sum;
+        for p_value in p_values {
+            assert!((p_value - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn bonferroni_caps_at_one() {
+        let mut p_values = vec![0.3, 0.5];
+        adjust(&AdjustmentMethod::Bonferroni, &mut p_values, 5);
+
+        assert!((p_values[0] - 1.0).abs() < 1e-9);
+        assert!((p_values[1] - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,532 @@
+use crate::adjustment::{self, AdjustmentMethod};
+use crate::correlation::{self, get_correlation_method, Correlation, CorResult, CorrelationMethod};
+use crate::dataset::{Dataset, DatasetRow};
+use crate::export::{self, AnalysisExport};
+use crate::partial_correlation;
+use crate::result_store::ResultStore;
+use extsort::{ExternalSorter, Sortable};
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::io::{Read, Write};
+
+/// Parameters for a Gene x GEM correlation analysis and the entry point (`compute`) that runs it.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub gene_file_path: String,
+    pub gem_file_path: String,
+    pub gem_contains_cpg: bool,
+    pub correlation_method: CorrelationMethod,
+    pub correlation_threshold: f64,
+    pub sort_buf_size: usize,
+    pub adjustment_method: AdjustmentMethod,
+    pub is_all_vs_all: bool,
+    pub collect_gem_dataset: Option<bool>,
+    pub keep_top_n: Option<usize>,
+    /// Caps the size of the rayon thread pool used to evaluate correlations. `None` lets rayon
+    /// size the pool to all available cores.
+    pub threads: Option<usize>,
+    /// Optional path to a covariates dataset (same row format as the Gene/GEM files: an
+    /// identifier column followed by per-sample values). When set, every correlation is computed
+    /// as a partial correlation conditioned on these covariate rows instead of a plain one.
+    /// Partial correlation is only implemented for `CorrelationMethod::Pearson`; `compute` returns
+    /// a `PyValueError` if this is set together with any other method rather than silently
+    /// ignoring `correlation_method`.
+    pub covariate_file_path: Option<String>,
+    /// Optional path to an LMDB environment. When set, every result that clears
+    /// `correlation_threshold` is persisted there (keyed by gene/GEM/CpG Site ID) as it's produced
+    /// during the sweep, independently of `keep_top_n` — so a large all-vs-all run can be queried
+    /// later via `result_store::ResultStore` (e.g. "every result for gene X") without recomputing
+    /// it, even though the `Vec<CorResult>` `compute` returns is still top-N-trimmed.
+    pub result_db_path: Option<String>,
+}
+
+/// Number of pending results a sweep worker buffers before flushing them to the `ResultStore` in
+/// one `put_all` transaction. Bounds how much RAM the LMDB write path holds onto per worker
+/// without round-tripping to a single-row transaction for every correlated pair.
+const RESULT_STORE_BATCH_SIZE: usize = 10_000;
+
+impl Analysis {
+    /// Runs the full sweep and returns the (possibly top-N-filtered) results together with the
+    /// total number of Gene/GEM combinations in the dataset and the number actually evaluated.
+    pub fn compute(&self) -> PyResult<(Vec<CorResult>, usize, usize)> {
+        if self.covariate_file_path.is_some()
+            && !matches!(self.correlation_method, CorrelationMethod::Pearson)
+        {
+            return Err(PyValueError::new_err(format!(
+                "covariate_file_path requires correlation_method = CorrelationMethod::Pearson \
+                 (partial correlation is not implemented for {})",
+                self.correlation_method
+            )));
+        }
+
+        let gene_dataset = Dataset::new(self.gene_file_path.clone(), false);
+        let gene_rows = gene_dataset.read_all()?;
+        let n = gene_dataset.number_of_samples()?;
+        let gem_dataset = Dataset::new(self.gem_file_path.clone(), self.gem_contains_cpg);
+        let covariates = self.load_covariates(n)?;
+
+        let result_store = match &self.result_db_path {
+            Some(result_db_path) => Some(ResultStore::open(result_db_path)?),
+            None => None,
+        };
+
+        let (total_combinations_count, filtered) = if self.collect_gem_dataset == Some(true) {
+            self.compute_parallel(&gene_rows, &gem_dataset, n, &covariates, result_store.as_ref())?
+        } else {
+            self.compute_sequential_disk(
+                &gene_rows,
+                &gem_dataset,
+                n,
+                &covariates,
+                result_store.as_ref(),
+            )?
+        };
+
+        let number_of_combinations_evaluated = total_combinations_count;
+        let adjusted = self.adjust_p_values(filtered, number_of_combinations_evaluated)?;
+
+        Ok((adjusted, total_combinations_count, number_of_combinations_evaluated))
+    }
+
+    /// Reads the covariate rows (if `covariate_file_path` is set) into plain per-sample value
+    /// vectors, dropping their identifiers since `partial_correlation::partial_correlate` only
+    /// needs the numeric columns. Validates every covariate has exactly `n` samples (the same
+    /// sample count as the Gene dataset) before returning, since `partial_correlate` hands these
+    /// straight to `rgsl`'s correlation FFI call with `n` as the element count — a shorter
+    /// covariate vector would make that call read past the end of its backing `Vec<f64>`.
+    fn load_covariates(&self, n: usize) -> PyResult<Vec<Vec<f64>>> {
+        let path = match &self.covariate_file_path {
+            Some(path) => path,
+            None => return Ok(Vec::new()),
+        };
+
+        let covariates: Vec<Vec<f64>> = Dataset::new(path.clone(), false)
+            .read_all()?
+            .into_iter()
+            .map(|row| row.values)
+            .collect();
+
+        if let Some(mismatched) = covariates.iter().find(|c| c.len() != n) {
+            return Err(PyValueError::new_err(format!(
+                "Covariate dataset '{path}' has a row with {} samples, expected {n} (the Gene dataset's sample count)",
+                mismatched.len()
+            )));
+        }
+
+        Ok(covariates)
+    }
+
+    /// Correlates a single Gene/GEM pair, going through `partial_correlation::partial_correlate`
+    /// when covariates are configured and falling back to the plain `Correlation` method
+    /// otherwise. Returns `None` when the pair should be skipped entirely (below threshold, or a
+    /// partial correlation that turned out to be singular).
+    fn evaluate_pair(
+        &self,
+        correlation_method: &dyn Correlation,
+        covariates: &[Vec<f64>],
+        x: &[f64],
+        y: &[f64],
+    ) -> Option<(f64, f64, Option<f64>)> {
+        let (r, p_value) = if covariates.is_empty() {
+            correlation_method.correlate(x, y)
+        } else {
+            partial_correlation::partial_correlate(x, y, covariates)?
+        };
+
+        if r.abs() < self.correlation_threshold {
+            return None;
+        }
+
+        // Only meaningful for Pearson/Spearman, where x and y are on a scale the unnormalized
+        // covariance is interpretable on, and only when `r`/`p_value` above are the plain
+        // (unconditioned) correlation: with covariates set, they're a partial correlation, and
+        // the marginal covariance of `x`/`y` alone would describe a different quantity than the
+        // `r` sitting next to it on the same `CorResult`.
+        let covariance = match self.correlation_method {
+            CorrelationMethod::Pearson | CorrelationMethod::Spearman if covariates.is_empty() => {
+                Some(correlation::covariance(x, y))
+            }
+            _ => None,
+        };
+
+        Some((r, p_value, covariance))
+    }
+
+    /// RAM-backed parallel path: the whole GEM dataset is collected up-front so that each rayon
+    /// worker can index into it freely, then the Gene rows are partitioned into chunks of
+    /// `rows / workers` rows (at least one), so the chunk count need not equal the worker count
+    /// when the row count isn't a multiple of it. Each chunk gets its own `Correlation` instance and accumulates a thread-local
+    /// top-N heap, which is merged into a single heap once every chunk has finished.
+    ///
+    /// When `result_store` is set, every result that clears `correlation_threshold` is also
+    /// persisted there in batches of `RESULT_STORE_BATCH_SIZE`, as it's produced and before
+    /// `push_top_n` can evict it from the in-RAM top-N heap — so the store ends up holding every
+    /// surviving result, not just the global top N.
+    fn compute_parallel(
+        &self,
+        gene_rows: &[DatasetRow],
+        gem_dataset: &Dataset,
+        n: usize,
+        covariates: &[Vec<f64>],
+        result_store: Option<&ResultStore>,
+    ) -> PyResult<(usize, Vec<CorResult>)> {
+        let gem_rows = gem_dataset.read_all()?;
+        let total_combinations_count = if self.is_all_vs_all {
+            gene_rows.len() * gem_rows.len()
+        } else {
+            gene_rows.len().min(gem_rows.len())
+        };
+
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.threads.unwrap_or(0))
+            .build()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let chunk_size = (gene_rows.len() / pool.current_num_threads().max(1)).max(1);
+
+        let heaps: Vec<BinaryHeap<TopN>> = pool.install(|| {
+            gene_rows
+                .par_chunks(chunk_size)
+                .enumerate()
+                .map(|(chunk_index, chunk)| -> PyResult<BinaryHeap<TopN>> {
+                    let correlation_method = get_correlation_method(&self.correlation_method, n);
+                    let mut heap: BinaryHeap<TopN> = BinaryHeap::new();
+                    let mut pending: Vec<CorResult> = Vec::new();
+
+                    for (offset, gene_row) in chunk.iter().enumerate() {
+                        let gene_index = chunk_index * chunk_size + offset;
+                        let gem_candidates: &[DatasetRow] = if self.is_all_vs_all {
+                            &gem_rows
+                        } else {
+                            gem_rows.get(gene_index).map_or(&[], std::slice::from_ref)
+                        };
+
+                        for gem_row in gem_candidates {
+                            let (r, p_value, covariance) = match self.evaluate_pair(
+                                correlation_method.as_ref(),
+                                covariates,
+                                &gene_row.values,
+                                &gem_row.values,
+                            ) {
+                                Some(rp) => rp,
+                                None => continue,
+                            };
+
+                            let result = CorResult {
+                                gene: gene_row.identifier.clone(),
+                                gem: gem_row.identifier.clone(),
+                                cpg_site_id: gem_row.cpg_site_id.clone(),
+                                correlation: Some(r),
+                                p_value: Some(p_value),
+                                adjusted_p_value: None,
+                                covariance,
+                            };
+
+                            if let Some(store) = result_store {
+                                pending.push(result.clone());
+                                if pending.len() >= RESULT_STORE_BATCH_SIZE {
+                                    store.put_all(&pending)?;
+                                    pending.clear();
+                                }
+                            }
+
+                            push_top_n(&mut heap, result, self.keep_top_n);
+                        }
+                    }
+
+                    if let Some(store) = result_store {
+                        if !pending.is_empty() {
+                            store.put_all(&pending)?;
+                        }
+                    }
+
+                    Ok(heap)
+                })
+                .collect::<PyResult<Vec<BinaryHeap<TopN>>>>()
+        })?;
+
+        let mut merged: BinaryHeap<TopN> = BinaryHeap::new();
+        for heap in heaps {
+            for entry in heap {
+                push_top_n(&mut merged, entry.0, self.keep_top_n);
+            }
+        }
+
+        Ok((
+            total_combinations_count,
+            merged.into_iter().map(|entry| entry.0).collect(),
+        ))
+    }
+
+    /// Disk-streaming fallback used when the GEM dataset hasn't been collected into RAM. Re-reads
+    /// the GEM file from disk for every Gene row (or once, zipped against the Gene rows, in
+    /// paired mode), so it stays sequential rather than feeding the rayon pool above.
+    ///
+    /// When `result_store` is set, every result that clears `correlation_threshold` is also
+    /// persisted there in batches of `RESULT_STORE_BATCH_SIZE`, as it's produced and before
+    /// `push_top_n` can evict it from the in-RAM top-N heap — so the store ends up holding every
+    /// surviving result, not just the global top N.
+    fn compute_sequential_disk(
+        &self,
+        gene_rows: &[DatasetRow],
+        gem_dataset: &Dataset,
+        n: usize,
+        covariates: &[Vec<f64>],
+        result_store: Option<&ResultStore>,
+    ) -> PyResult<(usize, Vec<CorResult>)> {
+        let correlation_method = get_correlation_method(&self.correlation_method, n);
+        let mut heap: BinaryHeap<TopN> = BinaryHeap::new();
+        let mut pending: Vec<CorResult> = Vec::new();
+        let mut total_combinations_count = 0usize;
+
+        if self.is_all_vs_all {
+            for gene_row in gene_rows {
+                for gem_row in gem_dataset.iter_rows()? {
+                    let gem_row = gem_row?;
+                    total_combinations_count += 1;
+
+                    let (r, p_value, covariance) = match self.evaluate_pair(
+                        correlation_method.as_ref(),
+                        covariates,
+                        &gene_row.values,
+                        &gem_row.values,
+                    ) {
+                        Some(rp) => rp,
+                        None => continue,
+                    };
+
+                    let result = CorResult {
+                        gene: gene_row.identifier.clone(),
+                        gem: gem_row.identifier.clone(),
+                        cpg_site_id: gem_row.cpg_site_id.clone(),
+                        correlation: Some(r),
+                        p_value: Some(p_value),
+                        adjusted_p_value: None,
+                        covariance,
+                    };
+
+                    if let Some(store) = result_store {
+                        pending.push(result.clone());
+                        if pending.len() >= RESULT_STORE_BATCH_SIZE {
+                            store.put_all(&pending)?;
+                            pending.clear();
+                        }
+                    }
+
+                    push_top_n(&mut heap, result, self.keep_top_n);
+                }
+            }
+        } else {
+            for (gene_row, gem_row) in gene_rows.iter().zip(gem_dataset.iter_rows()?) {
+                let gem_row = gem_row?;
+                total_combinations_count += 1;
+
+                let (r, p_value, covariance) = match self.evaluate_pair(
+                    correlation_method.as_ref(),
+                    covariates,
+                    &gene_row.values,
+                    &gem_row.values,
+                ) {
+                    Some(rp) => rp,
+                    None => continue,
+                };
+
+                let result = CorResult {
+                    gene: gene_row.identifier.clone(),
+                    gem: gem_row.identifier.clone(),
+                    cpg_site_id: gem_row.cpg_site_id.clone(),
+                    correlation: Some(r),
+                    p_value: Some(p_value),
+                    adjusted_p_value: None,
+                    covariance,
+                };
+
+                if let Some(store) = result_store {
+                    pending.push(result.clone());
+                    if pending.len() >= RESULT_STORE_BATCH_SIZE {
+                        store.put_all(&pending)?;
+                        pending.clear();
+                    }
+                }
+
+                push_top_n(&mut heap, result, self.keep_top_n);
+            }
+        }
+
+        if let Some(store) = result_store {
+            if !pending.is_empty() {
+                store.put_all(&pending)?;
+            }
+        }
+
+        Ok((total_combinations_count, heap.into_iter().map(|entry| entry.0).collect()))
+    }
+
+    /// Sorts the surviving results by p-value (spilling to disk via `extsort` once `sort_buf_size`
+    /// is exceeded, so very large result sets don't have to fit in RAM just to be sorted) and
+    /// applies the configured `AdjustmentMethod` in a single backward pass over that order. `m` is
+    /// the number of combinations evaluated, including those filtered out before this pass.
+    fn adjust_p_values(&self, results: Vec<CorResult>, m: usize) -> PyResult<Vec<CorResult>> {
+        if results.is_empty() {
+            return Ok(results);
+        }
+
+        let sorter = ExternalSorter::new().with_segment_size(self.sort_buf_size);
+        let sorted_iter = sorter
+            .sort(results.into_iter().map(ByPValue))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let mut sorted: Vec<CorResult> = sorted_iter.map(|ByPValue(result)| result).collect();
+
+        let mut p_values: Vec<f64> = sorted.iter().map(|r| r.p_value.unwrap_or(1.0)).collect();
+        adjustment::adjust(&self.adjustment_method, &mut p_values, m);
+
+        for (result, adjusted_p_value) in sorted.iter_mut().zip(p_values) {
+            result.adjusted_p_value = Some(adjusted_p_value);
+        }
+
+        Ok(sorted)
+    }
+
+    /// Writes a previously computed result set (as returned by `compute`) to newline-delimited
+    /// JSON at `path`. See `export::write_json` for the on-disk format.
+    pub fn export_json(
+        results: &[CorResult],
+        total_combinations_count: usize,
+        number_of_combinations_evaluated: usize,
+        path: &str,
+    ) -> PyResult<()> {
+        export::write_json(
+            path,
+            &AnalysisExport {
+                results: results.to_vec(),
+                total_combinations_count,
+                number_of_combinations_evaluated,
+            },
+        )
+    }
+
+    /// Reads a result set back from a file written by `export_json`.
+    pub fn import_json(path: &str) -> PyResult<(Vec<CorResult>, usize, usize)> {
+        let export = export::read_json(path)?;
+        Ok((
+            export.results,
+            export.total_combinations_count,
+            export.number_of_combinations_evaluated,
+        ))
+    }
+
+    /// Writes a previously computed result set to a single Python-compatible pickle stream at
+    /// `path`. See `export::write_pickle`.
+    pub fn export_pickle(
+        results: &[CorResult],
+        total_combinations_count: usize,
+        number_of_combinations_evaluated: usize,
+        path: &str,
+    ) -> PyResult<()> {
+        export::write_pickle(
+            path,
+            &AnalysisExport {
+                results: results.to_vec(),
+                total_combinations_count,
+                number_of_combinations_evaluated,
+            },
+        )
+    }
+
+    /// Reads a result set back from a file written by `export_pickle`.
+    pub fn import_pickle(path: &str) -> PyResult<(Vec<CorResult>, usize, usize)> {
+        let export = export::read_pickle(path)?;
+        Ok((
+            export.results,
+            export.total_combinations_count,
+            export.number_of_combinations_evaluated,
+        ))
+    }
+}
+
+/// Min-heap wrapper ordering `CorResult`s by absolute correlation, used to keep the top N
+/// strongest correlations seen so far (per-thread, then merged) without sorting the full result
+/// set. Ordering is reversed so the `BinaryHeap` (a max-heap) surfaces the *weakest* entry at
+/// `peek`/`pop`, which is the one to evict once the heap holds `keep_top_n` entries.
+struct TopN(CorResult);
+
+impl PartialEq for TopN {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.abs_correlation() == other.0.abs_correlation()
+    }
+}
+
+impl Eq for TopN {}
+
+impl PartialOrd for TopN {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopN {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .abs_correlation()
+            .partial_cmp(&self.0.abs_correlation())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+fn push_top_n(heap: &mut BinaryHeap<TopN>, result: CorResult, keep_top_n: Option<usize>) {
+    match keep_top_n {
+        Some(top_n) => {
+            if heap.len() < top_n {
+                heap.push(TopN(result));
+            } else if let Some(weakest) = heap.peek() {
+                if result.abs_correlation() > weakest.0.abs_correlation() {
+                    heap.pop();
+                    heap.push(TopN(result));
+                }
+            }
+        }
+        None => heap.push(TopN(result)),
+    }
+}
+
+/// Wraps a `CorResult` so it can be fed to `extsort`, which sorts by `Ord` rather than an
+/// arbitrary key function.
+struct ByPValue(CorResult);
+
+impl PartialEq for ByPValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.p_value == other.0.p_value
+    }
+}
+
+impl Eq for ByPValue {}
+
+impl PartialOrd for ByPValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByPValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .p_value
+            .partial_cmp(&other.0.p_value)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Sortable for ByPValue {
+    fn encode<W: Write>(&self, writer: &mut W) {
+        self.0.encode(writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Option<Self> {
+        CorResult::decode(reader).map(ByPValue)
+    }
+}
@@ -38,6 +38,10 @@ pub struct CorResult {
     /// Adjusted p-value (Benjamini-Hochberg, Benjamini-Yekutieli or Bonferroni, as selected)
     #[pyo3(get, set)]
     pub adjusted_p_value: Option<f64>,
+    /// Unnormalized association strength (Σ (x_i − x̄)(y_i − ȳ)) / (n − 1). Only filled for
+    /// Pearson and Spearman, where the underlying values are on a scale covariance is meaningful for.
+    #[pyo3(get, set)]
+    pub covariance: Option<f64>,
 }
 
 #[pymethods]
@@ -57,6 +61,7 @@ impl CorResult {
                 correlation: args.get_item(3).unwrap().extract::<Option<f64>>().unwrap(),
                 p_value: args.get_item(4).unwrap().extract::<Option<f64>>().unwrap(),
                 adjusted_p_value: args.get_item(5).unwrap().extract::<Option<f64>>().unwrap(),
+                covariance: args.get_item(6).unwrap().extract::<Option<f64>>().unwrap(),
             }
         } else {
             CorResult {
@@ -66,6 +71,7 @@ impl CorResult {
                 correlation: None,
                 p_value: None,
                 adjusted_p_value: None,
+                covariance: None,
             }
         }
     }
@@ -87,6 +93,8 @@ impl CorResult {
                 let adjusted_p_value_bytes =
                     args.get_item(5).unwrap().extract::<&PyBytes>().unwrap();
                 self.adjusted_p_value = deserialize(adjusted_p_value_bytes.as_bytes()).unwrap();
+                let covariance_bytes = args.get_item(6).unwrap().extract::<&PyBytes>().unwrap();
+                self.covariance = deserialize(covariance_bytes.as_bytes()).unwrap();
                 Ok(())
             }
             Err(e) => Err(e),
@@ -102,6 +110,7 @@ impl CorResult {
             PyBytes::new(py, &serialize(&self.correlation).unwrap()),
             PyBytes::new(py, &serialize(&self.p_value).unwrap()),
             PyBytes::new(py, &serialize(&self.adjusted_p_value).unwrap()),
+            PyBytes::new(py, &serialize(&self.covariance).unwrap()),
         )
             .to_object(py);
         Ok(obj)
@@ -128,13 +137,14 @@ impl CorResult {
     // Will be auto-generated by PyO3
     pub fn __repr__(&self) -> String {
         format!(
-            r#"CorResult("{}", "{}", "{}", {}, {:+e}, {:+e})"#,
+            r#"CorResult("{}", "{}", "{}", {}, {:+e}, {:+e}, {})"#,
             self.gene,
             self.gem,
             self.cpg_site_id_description(),
             self.correlation.unwrap_or(0.0),
             self.p_value.unwrap_or(0.0),
-            self.adjusted_p_value.unwrap_or(0.0)
+            self.adjusted_p_value.unwrap_or(0.0),
+            self.covariance.unwrap_or(0.0)
         )
     }
 }
@@ -146,13 +156,15 @@ impl std::fmt::Display for CorResult {
             r#"Gene: "{}" | GEM: "{}" | CpG Site ID: "{}"
     Cor: {}
     P-value: {:+e}
-    Adjusted p-value: {:+e}"#,
+    Adjusted p-value: {:+e}
+    Covariance: {}"#,
             self.gene,
             self.gem,
             self.cpg_site_id_description(),
             self.correlation.unwrap_or(0.0),
             self.p_value.unwrap_or(0.0),
-            self.adjusted_p_value.unwrap_or(0.0)
+            self.adjusted_p_value.unwrap_or(0.0),
+            self.covariance.unwrap_or(0.0)
         )
     }
 }
@@ -174,6 +186,23 @@ pub trait Correlation: Sync {
     fn correlate(&self, x: &[f64], y: &[f64]) -> (f64, f64);
 }
 
+/// Sample covariance, i.e. the mean of centered products: (Σ (x_i − x̄)(y_i − ȳ)) / (n − 1).
+/// Consistent with the Pearson `r` already produced by `correlation`, but unnormalized by the
+/// standard deviations. `x` and `y` are already `f64` by the time they reach here (dataset
+/// ingestion casts integer-valued CSV columns to `f64` before any mean/centering step), so this
+/// never truncates integer expression counts the way summing in an integer type would.
+pub fn covariance(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+
+    x.iter()
+        .zip(y.iter())
+        .map(|(xi, yi)| (xi - mean_x) * (yi - mean_y))
+        .sum::<f64>()
+        / (n - 1.0)
+}
+
 pub struct Pearson {
     n: usize,
     degrees_of_freedom: f64,
@@ -233,6 +262,88 @@ impl Correlation for Spearman {
     }
 }
 
+struct Bicor {
+    n: usize,
+    degrees_of_freedom: f64,
+}
+
+impl Bicor {
+    fn new(n: usize) -> Self {
+        Bicor {
+            n,
+            degrees_of_freedom: (n - 2) as f64,
+        }
+    }
+}
+
+impl Correlation for Bicor {
+    fn correlate(&self, x: &[f64], y: &[f64]) -> (f64, f64) {
+        let r = match (biweight_normalize(x), biweight_normalize(y)) {
+            (Some(x_tilde), Some(y_tilde)) => {
+                x_tilde.iter().zip(y_tilde.iter()).map(|(xi, yi)| xi * yi).sum()
+            }
+            // MAD collapses to zero for a constant (or near-constant) vector, so the biweight
+            // scaling is undefined; fall back to Pearson on that pair instead of dividing by zero.
+            _ => correlation(x, 1, y, 1, self.n),
+        };
+
+        // P-value (two-sided), same t-distribution path as Pearson
+        let statistic = self.degrees_of_freedom.sqrt() * r / (1.0 - r.powi(2)).sqrt();
+        let p_value = 2.0
+            * tdist_P(statistic, self.degrees_of_freedom)
+                .min(tdist_Q(statistic, self.degrees_of_freedom));
+
+        (r, p_value)
+    }
+}
+
+/// Biweight-normalizes a vector: centers it on the median, weights each deviation by the
+/// Tukey biweight function and rescales so the normalized vector has unit norm. Returns `None`
+/// when the median absolute deviation is zero (constant or near-constant input), since the
+/// weights would require dividing by zero.
+fn biweight_normalize(x: &[f64]) -> Option<Vec<f64>> {
+    let m = median(x);
+    let deviations: Vec<f64> = x.iter().map(|xi| xi - m).collect();
+    let mad = median(&deviations.iter().map(|d| d.abs()).collect::<Vec<f64>>());
+
+    if mad == 0.0 {
+        return None;
+    }
+
+    let weighted: Vec<f64> = deviations
+        .iter()
+        .map(|d| {
+            let u = d / (9.0 * mad);
+            if u.abs() < 1.0 {
+                d * (1.0 - u.powi(2)).powi(2)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let norm = weighted.iter().map(|w| w.powi(2)).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+
+    Some(weighted.iter().map(|w| w / norm).collect())
+}
+
+/// Median of a slice of `f64`. Copies and sorts its input, so callers on a hot path should
+/// reuse the result rather than recomputing it.
+fn median(x: &[f64]) -> f64 {
+    let mut sorted = x.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
 struct Kendall {}
 
 impl Kendall {
@@ -261,6 +372,7 @@ pub enum CorrelationMethod {
     Spearman = 1,
     Kendall = 2,
     Pearson = 3,
+    Bicor = 4,
 }
 
 impl std::fmt::Display for CorrelationMethod {
@@ -269,6 +381,7 @@ impl std::fmt::Display for CorrelationMethod {
             CorrelationMethod::Spearman => "Spearman",
             CorrelationMethod::Kendall => "Kendall",
             CorrelationMethod::Pearson => "Pearson",
+            CorrelationMethod::Bicor => "Bicor",
         };
 
         write!(f, "{description}")
@@ -283,5 +396,31 @@ pub fn get_correlation_method(
         CorrelationMethod::Pearson => Box::new(Pearson::new(number_of_samples)),
         CorrelationMethod::Spearman => Box::new(Spearman::new(number_of_samples)),
         CorrelationMethod::Kendall => Box::new(Kendall::new(number_of_samples)),
+        CorrelationMethod::Bicor => Box::new(Bicor::new(number_of_samples)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bicor_falls_back_to_pearson_when_mad_collapses() {
+        // Median is 1.0 and four of the five deviations from it are zero, so the MAD is zero
+        // and the biweight scaling is undefined for x.
+        let x = vec![1.0, 1.0, 1.0, 1.0, 2.0];
+        let y = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let n = x.len();
+
+        let (bicor_r, _) = Bicor::new(n).correlate(&x, &y);
+        let pearson_r = correlation(&x, 1, &y, 1, n);
+
+        assert!((bicor_r - pearson_r).abs() < 1e-9);
+    }
+
+    #[test]
+    fn median_of_odd_and_even_length_slices() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), 2.5);
     }
 }
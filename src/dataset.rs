@@ -0,0 +1,114 @@
+use pyo3::exceptions::PyIOError;
+use pyo3::PyResult;
+
+/// A single row of a Gene or GEM CSV dataset: an identifier (gene/GEM name), an optional
+/// CpG Site ID column (present when the GEM dataset is a methylation dataset) and the
+/// per-sample numeric values.
+#[derive(Clone, Debug)]
+pub struct DatasetRow {
+    pub identifier: String,
+    pub cpg_site_id: Option<String>,
+    pub values: Vec<f64>,
+}
+
+/// Lazily-accessible CSV dataset of Gene/GEM rows. The first column holds the row identifier,
+/// an optional second column holds the CpG Site ID, and the remaining columns are per-sample
+/// numeric values (read as `f64` regardless of whether the source column is integer- or
+/// float-valued, so counts never silently truncate).
+pub struct Dataset {
+    path: String,
+    contains_cpg: bool,
+}
+
+impl Dataset {
+    pub fn new(path: String, contains_cpg: bool) -> Self {
+        Dataset { path, contains_cpg }
+    }
+
+    fn reader(&self) -> PyResult<csv::Reader<std::fs::File>> {
+        csv::ReaderBuilder::new()
+            .has_headers(true)
+            .from_path(&self.path)
+            .map_err(|e| PyIOError::new_err(format!("Could not read '{}': {e}", self.path)))
+    }
+
+    /// Number of samples (columns after the identifier and, if present, the CpG Site ID).
+    pub fn number_of_samples(&self) -> PyResult<usize> {
+        let mut reader = self.reader()?;
+        let header_len = reader
+            .headers()
+            .map_err(|e| PyIOError::new_err(e.to_string()))?
+            .len();
+        let identifier_columns = if self.contains_cpg { 2 } else { 1 };
+        Ok(header_len.saturating_sub(identifier_columns))
+    }
+
+    fn parse_record(&self, record: &csv::StringRecord) -> PyResult<DatasetRow> {
+        let identifier = record
+            .get(0)
+            .ok_or_else(|| PyIOError::new_err("Missing identifier column"))?
+            .to_string();
+
+        let values_start = if self.contains_cpg { 2 } else { 1 };
+        let cpg_site_id = if self.contains_cpg {
+            record.get(1).map(|s| s.to_string())
+        } else {
+            None
+        };
+
+        let values = record
+            .iter()
+            .skip(values_start)
+            .map(|field| {
+                field
+                    .parse::<f64>()
+                    .map_err(|e| PyIOError::new_err(format!("Invalid numeric value '{field}': {e}")))
+            })
+            .collect::<PyResult<Vec<f64>>>()?;
+
+        Ok(DatasetRow {
+            identifier,
+            cpg_site_id,
+            values,
+        })
+    }
+
+    /// Reads every row into memory. Used for the Gene dataset (always) and for the GEM dataset
+    /// when `collect_gem_dataset` requests the RAM-backed path.
+    pub fn read_all(&self) -> PyResult<Vec<DatasetRow>> {
+        let mut reader = self.reader()?;
+        reader
+            .records()
+            .map(|record| {
+                let record = record.map_err(|e| PyIOError::new_err(e.to_string()))?;
+                self.parse_record(&record)
+            })
+            .collect()
+    }
+
+    /// Streams rows one at a time without collecting the whole dataset in memory. Used for the
+    /// GEM dataset when `collect_gem_dataset` is `false`/`None`.
+    pub fn iter_rows(&self) -> PyResult<impl Iterator<Item = PyResult<DatasetRow>> + '_> {
+        let reader = self.reader()?;
+        Ok(DatasetIter {
+            dataset: self,
+            records: reader.into_records(),
+        })
+    }
+}
+
+struct DatasetIter<'a> {
+    dataset: &'a Dataset,
+    records: csv::StringRecordsIntoIter<std::fs::File>,
+}
+
+impl<'a> Iterator for DatasetIter<'a> {
+    type Item = PyResult<DatasetRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records.next().map(|record| {
+            let record = record.map_err(|e| PyIOError::new_err(e.to_string()))?;
+            self.dataset.parse_record(&record)
+        })
+    }
+}
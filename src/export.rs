@@ -0,0 +1,197 @@
+use crate::correlation::CorResult;
+use pyo3::exceptions::PyIOError;
+use pyo3::PyResult;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+/// Combination counts alongside a sweep's results, carried as the first line of a
+/// newline-delimited JSON export and as part of the pickle payload.
+#[derive(Serialize, Deserialize)]
+struct Counts {
+    total_combinations_count: usize,
+    number_of_combinations_evaluated: usize,
+}
+
+/// The full export payload: every surviving `CorResult` plus the combination counts
+/// `Analysis::compute` returns alongside them.
+#[derive(Serialize, Deserialize)]
+pub struct AnalysisExport {
+    pub results: Vec<CorResult>,
+    pub total_combinations_count: usize,
+    pub number_of_combinations_evaluated: usize,
+}
+
+/// Writes `export` as newline-delimited JSON: a first line with the combination counts, followed
+/// by one line per `CorResult`. Lets non-Python consumers ingest an analysis's results without a
+/// pickle-compatible reader.
+pub fn write_json(path: &str, export: &AnalysisExport) -> PyResult<()> {
+    let mut writer = BufWriter::new(File::create(path).map_err(|e| PyIOError::new_err(e.to_string()))?);
+
+    let counts = Counts {
+        total_combinations_count: export.total_combinations_count,
+        number_of_combinations_evaluated: export.number_of_combinations_evaluated,
+    };
+    write_json_line(&mut writer, &counts)?;
+
+    for result in &export.results {
+        write_json_line(&mut writer, result)?;
+    }
+
+    Ok(())
+}
+
+fn write_json_line<W: Write, T: serde::Serialize>(writer: &mut W, value: &T) -> PyResult<()> {
+    serde_json::to_writer(&mut *writer, value).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    writer
+        .write_all(b"\n")
+        .map_err(|e| PyIOError::new_err(e.to_string()))
+}
+
+/// Reads back a file written by `write_json`.
+pub fn read_json(path: &str) -> PyResult<AnalysisExport> {
+    let file = File::open(path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let counts_line = lines
+        .next()
+        .ok_or_else(|| PyIOError::new_err("Empty JSON export"))?
+        .map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let counts: Counts =
+        serde_json::from_str(&counts_line).map_err(|e| PyIOError::new_err(e.to_string()))?;
+
+    let results = lines
+        .map(|line| {
+            let line = line.map_err(|e| PyIOError::new_err(e.to_string()))?;
+            serde_json::from_str(&line).map_err(|e| PyIOError::new_err(e.to_string()))
+        })
+        .collect::<PyResult<Vec<CorResult>>>()?;
+
+    Ok(AnalysisExport {
+        results,
+        total_combinations_count: counts.total_combinations_count,
+        number_of_combinations_evaluated: counts.number_of_combinations_evaluated,
+    })
+}
+
+/// Writes `export` as a single Python-compatible pickle stream, so Python callers can round-trip
+/// an entire result list with one `pickle.load` rather than pickling `CorResult`s one at a time
+/// via `__getstate__`/`__setstate__`.
+pub fn write_pickle(path: &str, export: &AnalysisExport) -> PyResult<()> {
+    let writer = BufWriter::new(File::create(path).map_err(|e| PyIOError::new_err(e.to_string()))?);
+    serde_pickle::to_writer(writer, export, Default::default())
+        .map_err(|e| PyIOError::new_err(e.to_string()))
+}
+
+/// Reads back a file written by `write_pickle`.
+pub fn read_pickle(path: &str) -> PyResult<AnalysisExport> {
+    let reader = BufReader::new(File::open(path).map_err(|e| PyIOError::new_err(e.to_string()))?);
+    serde_pickle::from_reader(reader, Default::default()).map_err(|e| PyIOError::new_err(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_export() -> AnalysisExport {
+        AnalysisExport {
+            results: vec![
+                CorResult {
+                    gene: "BRCA1".to_string(),
+                    gem: "hsa-miR-21".to_string(),
+                    cpg_site_id: Some("cg00000001".to_string()),
+                    correlation: Some(0.8),
+                    p_value: Some(0.01),
+                    adjusted_p_value: Some(0.02),
+                    covariance: Some(1.5),
+                },
+                CorResult {
+                    gene: "BRCA2".to_string(),
+                    gem: "hsa-miR-22".to_string(),
+                    cpg_site_id: None,
+                    correlation: None,
+                    p_value: None,
+                    adjusted_p_value: None,
+                    covariance: None,
+                },
+            ],
+            total_combinations_count: 100,
+            number_of_combinations_evaluated: 42,
+        }
+    }
+
+    fn temp_path(name: &str) -> String {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir()
+            .join(format!(
+                "ggca-export-test-{name}-{}-{unique}",
+                std::process::id()
+            ))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn json_round_trip_preserves_results_and_counts() {
+        let path = temp_path("json");
+        let export = sample_export();
+
+        write_json(&path, &export).unwrap();
+        let read_back = read_json(&path).unwrap();
+
+        assert_eq!(read_back.results, export.results);
+        assert_eq!(
+            read_back.total_combinations_count,
+            export.total_combinations_count
+        );
+        assert_eq!(
+            read_back.number_of_combinations_evaluated,
+            export.number_of_combinations_evaluated
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn json_export_writes_counts_as_the_first_line() {
+        let path = temp_path("json-counts-line");
+        write_json(&path, &sample_export()).unwrap();
+
+        let first_line = BufReader::new(File::open(&path).unwrap())
+            .lines()
+            .next()
+            .unwrap()
+            .unwrap();
+        let counts: Counts = serde_json::from_str(&first_line).unwrap();
+
+        assert_eq!(counts.total_combinations_count, 100);
+        assert_eq!(counts.number_of_combinations_evaluated, 42);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn pickle_round_trip_preserves_results_and_counts() {
+        let path = temp_path("pickle");
+        let export = sample_export();
+
+        write_pickle(&path, &export).unwrap();
+        let read_back = read_pickle(&path).unwrap();
+
+        assert_eq!(read_back.results, export.results);
+        assert_eq!(
+            read_back.total_combinations_count,
+            export.total_combinations_count
+        );
+        assert_eq!(
+            read_back.number_of_combinations_evaluated,
+            export.number_of_combinations_evaluated
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
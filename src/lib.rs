@@ -0,0 +1,16 @@
+use pyo3::prelude::*;
+
+pub mod adjustment;
+pub mod analysis;
+pub mod correlation;
+mod dataset;
+pub mod export;
+pub mod partial_correlation;
+pub mod result_store;
+
+/// Python module exposing the correlation analysis result type.
+#[pymodule]
+fn ggca(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<correlation::CorResult>()?;
+    Ok(())
+}
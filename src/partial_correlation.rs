@@ -0,0 +1,174 @@
+use rgsl::randist::t_distribution::{tdist_P, tdist_Q};
+use rgsl::statistics::correlation;
+
+/// Computes the correlation between `x` and `y` conditioned on one or more covariate vectors
+/// (e.g. tissue, age, batch), so confounders shared by `x` and `y` don't inflate the reported
+/// association. With a single covariate this is the closed-form first-order formula; with more
+/// than one it goes through the precision-matrix formulation: build the (k+2)x(k+2) correlation
+/// matrix of `[x, y, covariates...]`, invert it to the precision matrix P, and read off
+/// `r = -P_ij / sqrt(P_ii * P_jj)`. Returns `None` when that correlation matrix is singular (for
+/// example a covariate collinear with `x` or `y`).
+pub fn partial_correlate(x: &[f64], y: &[f64], covariates: &[Vec<f64>]) -> Option<(f64, f64)> {
+    let n = x.len();
+    let k = covariates.len();
+
+    if k == 1 {
+        return partial_correlate_one_covariate(x, y, &covariates[0], n);
+    }
+
+    let vectors: Vec<&[f64]> = std::iter::once(x)
+        .chain(std::iter::once(y))
+        .chain(covariates.iter().map(|c| c.as_slice()))
+        .collect();
+    let dim = vectors.len();
+
+    let mut matrix = vec![vec![0.0; dim]; dim];
+    for i in 0..dim {
+        for j in 0..dim {
+            matrix[i][j] = if i == j {
+                1.0
+            } else {
+                correlation(vectors[i], 1, vectors[j], 1, n)
+            };
+        }
+    }
+
+    let precision = invert(&matrix)?;
+    let denominator = (precision[0][0] * precision[1][1]).sqrt();
+    if denominator == 0.0 {
+        return None;
+    }
+    let r = -precision[0][1] / denominator;
+
+    p_value_for(r, n, k)
+}
+
+fn partial_correlate_one_covariate(x: &[f64], y: &[f64], z: &[f64], n: usize) -> Option<(f64, f64)> {
+    let r_xy = correlation(x, 1, y, 1, n);
+    let r_xz = correlation(x, 1, z, 1, n);
+    let r_yz = correlation(y, 1, z, 1, n);
+
+    let one_minus_r_xz_sq = 1.0 - r_xz.powi(2);
+    let one_minus_r_yz_sq = 1.0 - r_yz.powi(2);
+    // Same near-singularity cutoff `invert` applies to its pivots: a covariate collinear with `x`
+    // or `y` leaves these a rounding error away from zero (or below it), not exactly zero.
+    if one_minus_r_xz_sq < 1e-12 || one_minus_r_yz_sq < 1e-12 {
+        return None;
+    }
+
+    let denominator = (one_minus_r_xz_sq * one_minus_r_yz_sq).sqrt();
+    let r = (r_xy - r_xz * r_yz) / denominator;
+    p_value_for(r, n, 1)
+}
+
+/// Two-sided t-test p-value for a partial correlation, against a t-distribution with
+/// `n - 2 - k` degrees of freedom, same path `Pearson` uses with `k = 0`.
+fn p_value_for(r: f64, n: usize, k: usize) -> Option<(f64, f64)> {
+    let degrees_of_freedom = (n as isize - 2 - k as isize) as f64;
+    if degrees_of_freedom <= 0.0 || !r.is_finite() {
+        return None;
+    }
+
+    let statistic = degrees_of_freedom.sqrt() * r / (1.0 - r.powi(2)).sqrt();
+    let p_value =
+        2.0 * tdist_P(statistic, degrees_of_freedom).min(tdist_Q(statistic, degrees_of_freedom));
+
+    Some((r, p_value))
+}
+
+/// Gauss-Jordan matrix inversion with partial pivoting. Returns `None` for singular (or
+/// near-singular, pivot below `1e-12`) matrices instead of dividing by zero.
+fn invert(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut augmented: Vec<Vec<f64>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut row = row.clone();
+            row.extend((0..n).map(|j| if i == j { 1.0 } else { 0.0 }));
+            row
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot_row = (col..n).max_by(|&a, &b| {
+            augmented[a][col]
+                .abs()
+                .partial_cmp(&augmented[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+
+        if augmented[pivot_row][col].abs() < 1e-12 {
+            return None;
+        }
+
+        augmented.swap(col, pivot_row);
+
+        let pivot = augmented[col][col];
+        for value in augmented[col].iter_mut() {
+            *value /= pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = augmented[row][col];
+            if factor == 0.0 {
+                continue;
+            }
+            for c in 0..2 * n {
+                augmented[row][c] -= factor * augmented[col][c];
+            }
+        }
+    }
+
+    Some(augmented.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_first_order_formula_for_one_covariate() {
+        let x = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let y = vec![2.0, 1.0, 4.0, 6.0, 5.0, 7.0];
+        let z = vec![5.0, 3.0, 6.0, 1.0, 2.0, 4.0];
+        let n = x.len();
+
+        let r_xy = correlation(&x, 1, &y, 1, n);
+        let r_xz = correlation(&x, 1, &z, 1, n);
+        let r_yz = correlation(&y, 1, &z, 1, n);
+        let expected_r =
+            (r_xy - r_xz * r_yz) / ((1.0 - r_xz.powi(2)) * (1.0 - r_yz.powi(2))).sqrt();
+
+        let (r, _) = partial_correlate(&x, &y, &[z]).unwrap();
+        assert!((r - expected_r).abs() < 1e-9);
+    }
+
+    #[test]
+    fn returns_none_when_covariate_is_collinear_with_x() {
+        let x = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let y = vec![2.0, 1.0, 4.0, 3.0, 6.0];
+        let z = x.clone(); // r_xz is 1.0 up to rounding, so the first-order denominator is (numerically) zero
+
+        assert!(partial_correlate(&x, &y, &[z]).is_none());
+    }
+
+    #[test]
+    fn invert_recovers_identity() {
+        let matrix = vec![vec![2.0, 0.0], vec![0.0, 4.0]];
+        let inverse = invert(&matrix).unwrap();
+
+        assert!((inverse[0][0] - 0.5).abs() < 1e-9);
+        assert!((inverse[1][1] - 0.25).abs() < 1e-9);
+        assert!(inverse[0][1].abs() < 1e-9);
+    }
+
+    #[test]
+    fn invert_returns_none_for_singular_matrix() {
+        let matrix = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        assert!(invert(&matrix).is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::correlation::CorResult;
+use extsort::Sortable;
+use lmdb::{Cursor, Environment, Transaction, WriteFlags};
+use pyo3::exceptions::PyIOError;
+use pyo3::PyResult;
+use std::io::Cursor as IoCursor;
+use std::path::Path;
+
+/// Embedded LMDB store for `CorResult`s, keyed by `(gene, gem, cpg_site_id)` so a very large
+/// all-vs-all sweep can spill its results to disk with bounded RAM, and a previous run's
+/// `result_db_path` can be re-opened later to query top correlations without recomputing them.
+/// Values reuse `CorResult`'s `Sortable` encoding (the same bincode payload used for the
+/// extsort-backed adjusted p-value pass), so there's a single encode/decode path for the type.
+pub struct ResultStore {
+    env: Environment,
+}
+
+impl ResultStore {
+    /// Default LMDB map size. This is a virtual address space reservation, not a disk commitment
+    /// (LMDB only grows the backing file as pages are actually written), so it's fine to size it
+    /// generously rather than tune it per dataset. The library default (10MiB) is far too small
+    /// for the all-vs-all runs this store exists for; 64GiB covers those comfortably on the 64-bit
+    /// platforms this crate targets.
+    const DEFAULT_MAP_SIZE: usize = 64 * 1024 * 1024 * 1024;
+
+    pub fn open(path: &str) -> PyResult<Self> {
+        Self::open_with_map_size(path, Self::DEFAULT_MAP_SIZE)
+    }
+
+    /// Like `open`, but with an explicit LMDB map size (in bytes) instead of `DEFAULT_MAP_SIZE`.
+    /// Useful to shrink it on platforms where reserving tens of GiB of address space up front is
+    /// undesirable, or to grow it further for datasets that would otherwise hit `MDB_MAP_FULL`.
+    pub fn open_with_map_size(path: &str, map_size: usize) -> PyResult<Self> {
+        std::fs::create_dir_all(path)
+            .map_err(|e| PyIOError::new_err(format!("Could not create '{path}': {e}")))?;
+        let env = Environment::new()
+            .set_map_size(map_size)
+            .open(Path::new(path))
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        Ok(ResultStore { env })
+    }
+
+    /// Persists every result in a single transaction, overwriting any prior entry with the same
+    /// `(gene, gem, cpg_site_id)` key.
+    pub fn put_all(&self, results: &[CorResult]) -> PyResult<()> {
+        let db = self
+            .env
+            .open_db(None)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        let mut txn = self
+            .env
+            .begin_rw_txn()
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+
+        for result in results {
+            let key = encode_key(&result.gene, &result.gem, result.cpg_site_id.as_deref());
+            let mut value = Vec::new();
+            result.encode(&mut value);
+            txn.put(db, &key, &value, WriteFlags::empty())
+                .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        }
+
+        txn.commit().map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Returns every stored result for a given Gene, collected into a `Vec` (not yielded lazily).
+    /// Relies on LMDB's key ordering to scan only the `gene\0`-prefixed range rather than the
+    /// whole database.
+    pub fn results_for_gene(&self, gene: &str) -> PyResult<Vec<CorResult>> {
+        let db = self
+            .env
+            .open_db(None)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        let txn = self
+            .env
+            .begin_ro_txn()
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        let mut cursor = txn
+            .open_ro_cursor(db)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+
+        let prefix = format!("{gene}\0");
+        let results = cursor
+            .iter_from(prefix.as_bytes())
+            .take_while(|entry| {
+                entry
+                    .as_ref()
+                    .map(|(key, _)| key.starts_with(prefix.as_bytes()))
+                    .unwrap_or(false)
+            })
+            .filter_map(|entry| entry.ok())
+            .filter_map(|(_, value)| CorResult::decode(&mut IoCursor::new(value)))
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Returns every stored result whose absolute correlation is at least `min_abs_correlation`,
+    /// collected into a `Vec` (not yielded lazily).
+    /// Correlation magnitude isn't part of the key, so this does a full scan of the database.
+    pub fn results_above(&self, min_abs_correlation: f64) -> PyResult<Vec<CorResult>> {
+        let db = self
+            .env
+            .open_db(None)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        let txn = self
+            .env
+            .begin_ro_txn()
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        let mut cursor = txn
+            .open_ro_cursor(db)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+
+        let results = cursor
+            .iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|(_, value)| CorResult::decode(&mut IoCursor::new(value)))
+            .filter(|result| result.correlation.is_some_and(|r| r.abs() >= min_abs_correlation))
+            .collect();
+
+        Ok(results)
+    }
+}
+
+fn encode_key(gene: &str, gem: &str, cpg_site_id: Option<&str>) -> Vec<u8> {
+    format!("{gene}\0{gem}\0{}", cpg_site_id.unwrap_or("")).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_result(gene: &str, gem: &str, correlation: f64) -> CorResult {
+        CorResult {
+            gene: gene.to_string(),
+            gem: gem.to_string(),
+            cpg_site_id: None,
+            correlation: Some(correlation),
+            p_value: Some(0.01),
+            adjusted_p_value: Some(0.02),
+            covariance: Some(1.5),
+        }
+    }
+
+    #[test]
+    fn put_and_read_back_round_trip() {
+        let dir = tempdir().unwrap();
+        let store = ResultStore::open(dir.path().to_str().unwrap()).unwrap();
+
+        let result = sample_result("BRCA1", "hsa-miR-21", 0.9);
+        store.put_all(&[result.clone()]).unwrap();
+
+        let fetched = store.results_for_gene("BRCA1").unwrap();
+        assert_eq!(fetched, vec![result]);
+    }
+
+    #[test]
+    fn results_for_gene_only_returns_matching_prefix() {
+        let dir = tempdir().unwrap();
+        let store = ResultStore::open(dir.path().to_str().unwrap()).unwrap();
+
+        store
+            .put_all(&[
+                sample_result("BRCA1", "hsa-miR-21", 0.9),
+                sample_result("BRCA1", "hsa-miR-22", 0.8),
+                sample_result("BRCA2", "hsa-miR-21", 0.7),
+            ])
+            .unwrap();
+
+        let fetched = store.results_for_gene("BRCA1").unwrap();
+        assert_eq!(fetched.len(), 2);
+        assert!(fetched.iter().all(|result| result.gene == "BRCA1"));
+    }
+
+    #[test]
+    fn results_above_filters_by_magnitude() {
+        let dir = tempdir().unwrap();
+        let store = ResultStore::open(dir.path().to_str().unwrap()).unwrap();
+
+        store
+            .put_all(&[
+                sample_result("BRCA1", "hsa-miR-21", 0.9),
+                sample_result("BRCA1", "hsa-miR-22", 0.2),
+                sample_result("BRCA2", "hsa-miR-21", -0.8),
+            ])
+            .unwrap();
+
+        let fetched = store.results_above(0.75).unwrap();
+        assert_eq!(fetched.len(), 2);
+        assert!(fetched
+            .iter()
+            .all(|result| result.correlation.unwrap().abs() >= 0.75));
+    }
+}